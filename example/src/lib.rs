@@ -81,10 +81,18 @@ mod tests {
 	use node_cli::chain_spec::development_config;
 	use sc_consensus_manual_seal::consensus::babe::BabeConsensusDataProvider;
 	use sp_consensus_babe::AuthorityId;
+	use sp_consensus_slots::Slot;
 	use sp_keyring::sr25519::Keyring::Alice;
 	use sp_runtime::{traits::IdentifyAccount, MultiSigner};
-	use std::sync::Arc;
-	use substrate_simnode::{build_node_subsystems, build_runtime, ConfigOrChainSpec};
+	use std::{
+		net::SocketAddr,
+		sync::{Arc, Mutex},
+	};
+	use substrate_simnode::{
+		build_node_subsystems, build_runtime, statement_store::build_node_subsystems_with_statement_store,
+		warp::{SlotOffset, WarpableNode},
+		ConfigOrChainSpec,
+	};
 
 	#[test]
 	fn substrate_simnode() {
@@ -159,4 +167,108 @@ mod tests {
 			let _client = node.client();
 		})
 	}
+
+	/// Exercises the finality/fork/warp/statement-store/RPC-server additions together on a
+	/// single node, the same way a test built against this crate would combine them.
+	#[test]
+	fn node_feature_additions() {
+		let tokio_runtime = build_runtime().unwrap();
+		let offset = SlotOffset::new();
+		let babe_link_parts = Arc::new(Mutex::new(None));
+
+		let (node, statement_store) = {
+			let offset = offset.clone();
+			let babe_link_parts = babe_link_parts.clone();
+			build_node_subsystems_with_statement_store::<NodeTemplateChainInfo, _>(
+				ConfigOrChainSpec::ChainSpec(
+					Box::new(development_config()),
+					tokio_runtime.handle().clone(),
+				),
+				false,
+				move |client, select_chain, keystore, _parachain_inherent_provider| {
+					let (grandpa_block_import, ..) = grandpa::block_import(
+						client.clone(),
+						&(client.clone() as Arc<_>),
+						select_chain.clone(),
+						None,
+					)?;
+
+					let slot_duration = sc_consensus_babe::Config::get_or_compute(&*client)?;
+					let (block_import, babe_link) = sc_consensus_babe::block_import(
+						slot_duration.clone(),
+						grandpa_block_import,
+						client.clone(),
+					)?;
+					*babe_link_parts.lock().unwrap() =
+						Some((babe_link.epoch_changes().clone(), slot_duration));
+
+					let consensus_data_provider = BabeConsensusDataProvider::new(
+						client.clone(),
+						keystore.sync_keystore(),
+						babe_link.epoch_changes().clone(),
+						vec![(AuthorityId::from(Alice.public()), 1000)],
+					)
+					.expect("failed to create ConsensusDataProvider");
+
+					let cloned_client = client.clone();
+					let offset = offset.clone();
+					let create_inherent_data_providers = Box::new(move |_, _| {
+						let client = cloned_client.clone();
+						let offset = offset.clone();
+						async move {
+							let timestamp = SlotTimestampProvider::babe(client.clone())
+								.map_err(|err| format!("{:?}", err))?;
+							let babe = sp_consensus_babe::inherents::InherentDataProvider::new(
+								offset.apply(timestamp.slot().into()),
+							);
+							Ok((timestamp, babe))
+						}
+					});
+
+					Ok((
+						block_import,
+						Some(Box::new(consensus_data_provider)),
+						create_inherent_data_providers,
+					))
+				},
+			)
+			.unwrap()
+		};
+		let (epoch_changes, babe_config) = babe_link_parts.lock().unwrap().clone().unwrap();
+		let warpable = WarpableNode::new(node, offset, epoch_changes, babe_config);
+
+		tokio_runtime.block_on(async {
+			// finality: seal a block, then explicitly finalize it.
+			warpable.seal_blocks(1).await;
+			let best_hash = warpable.client().info().best_hash;
+			warpable.finalize_block(best_hash).await;
+			assert_eq!(warpable.client().info().finalized_hash, best_hash);
+
+			// fork: seal a block on an explicit parent hash (here the now-finalized best block).
+			let created = warpable.seal_block_on(best_hash, true, false).await;
+			assert_ne!(created.hash, best_hash);
+
+			// warp: fast-forward far enough to cross at least one epoch boundary, without
+			// sealing every intervening slot by hand.
+			let target = Slot::from(u64::from(warpable.current_slot()) + 1_000);
+			let authored = warpable.warp_to_slot(target).await;
+			assert!(authored >= 2, "expected a boundary block plus the final landing block");
+			assert_eq!(warpable.current_slot(), target + 1);
+
+			// statement store: submit a statement locally and read it back.
+			let statement = sp_statement_store::Statement::new();
+			statement_store.submit_statement(statement);
+			assert_eq!(statement_store.statements().len(), 1);
+
+			// external RPC server: bind WS/HTTP and tear it down again.
+			let rpc_server = warpable
+				.start_rpc_server(
+					SocketAddr::from(([127, 0, 0, 1], 0)),
+					SocketAddr::from(([127, 0, 0, 1], 0)),
+				)
+				.unwrap();
+			assert_ne!(rpc_server.ws_addr().port(), 0);
+			assert_ne!(rpc_server.http_addr().port(), 0);
+		})
+	}
 }
@@ -18,16 +18,20 @@
 
 use crate::{
 	ChainInfo, FullClientFor, Node, ParachainInherentSproofProvider,
-	SharedParachainInherentProvider,
+	SharedParachainInherentProvider, SignatureVerificationOverride,
 };
 use futures::channel::mpsc;
 use manual_seal::{
+	consensus::{aura::AuraConsensusDataProvider, timestamp::SlotTimestampProvider},
 	import_queue,
 	rpc::{ManualSeal, ManualSealApi},
 	run_manual_seal, ConsensusDataProvider, ManualSealParams,
 };
 use sc_client_api::backend::Backend;
-use sc_executor::NativeElseWasmExecutor;
+use sc_executor::{
+	CodeExecutor, HeapAllocStrategy, NativeElseWasmExecutor, RuntimeVersionOf, WasmExecutor,
+	DEFAULT_HEAP_ALLOC_STRATEGY,
+};
 use sc_service::{
 	build_network, new_full_parts, spawn_tasks, BuildNetworkParams, Configuration,
 	KeystoreContainer, SpawnTasksParams, TFullBackend,
@@ -36,7 +40,9 @@ use sc_transaction_pool::BasicPool;
 use sp_api::{ApiExt, ConstructRuntimeApi, Core, Metadata, TransactionFor};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::HeaderBackend;
+use sp_consensus_aura::{inherents::InherentDataProvider as AuraInherentDataProvider, sr25519::AuthorityId as AuraId, AuraApi};
 use sp_inherents::CreateInherentDataProviders;
+use sp_io::SubstrateHostFunctions;
 use sp_offchain::OffchainWorkerApi;
 use sp_runtime::traits::{Block as BlockT, Header};
 use sp_session::SessionKeys;
@@ -100,10 +106,167 @@ where
 		config.max_runtime_instances,
 	);
 
+	let (node, _network, _keystore, ()) = build_node_subsystems_with_executor::<T, I, _, _>(
+		config,
+		is_parachain,
+		block_import_provider,
+		executor,
+		|_client| (Vec::new(), ()),
+	)?;
+	Ok(node)
+}
+
+/// Like [`build_node_subsystems`], but runs the runtime through a plain [`WasmExecutor`] instead
+/// of `ChainInfo::ExecutorDispatch`'s compiled-in native runtime.
+///
+/// Because the `WasmExecutor` executes the runtime code stored in the chain's state (starting
+/// with the `:code` in `config.chain_spec`'s genesis storage), this drives simnode against an
+/// arbitrary on-chain/production runtime blob without recompiling simnode against that runtime's
+/// crate — including running an upgraded blob, by pointing `config` at a chain spec whose genesis
+/// embeds it.
+pub fn build_node_subsystems_wasm<T, I>(
+	config: Configuration,
+	is_parachain: bool,
+	block_import_provider: I,
+) -> Result<Node<T>, sc_service::Error>
+where
+	T: ChainInfo + 'static,
+	<T::RuntimeApi as ConstructRuntimeApi<T::Block, FullClientFor<T>>>::RuntimeApi:
+		Core<T::Block>
+			+ Metadata<T::Block>
+			+ OffchainWorkerApi<T::Block>
+			+ SessionKeys<T::Block>
+			+ TaggedTransactionQueue<T::Block>
+			+ BlockBuilder<T::Block>
+			+ ApiExt<T::Block, StateBackend = <TFullBackend<T::Block> as Backend<T::Block>>::State>,
+	<T::Runtime as frame_system::Config>::Call: From<frame_system::Call<T::Runtime>>,
+	<<T as ChainInfo>::Block as BlockT>::Hash: FromStr + Unpin,
+	<<T as ChainInfo>::Block as BlockT>::Header: Unpin,
+	<<<T as ChainInfo>::Block as BlockT>::Header as Header>::Number:
+		num_traits::cast::AsPrimitive<usize> + num_traits::cast::AsPrimitive<u32>,
+	I: Fn(
+		Arc<FullClientFor<T>>,
+		sc_consensus::LongestChain<TFullBackend<T::Block>, T::Block>,
+		&KeystoreContainer,
+		Option<SharedParachainInherentProvider<T>>,
+	) -> Result<
+		(
+			T::BlockImport,
+			Option<
+				Box<
+					dyn ConsensusDataProvider<
+						T::Block,
+						Transaction = TransactionFor<FullClientFor<T>, T::Block>,
+					>,
+				>,
+			>,
+			Box<
+				dyn CreateInherentDataProviders<
+					T::Block,
+					(),
+					InherentDataProviders = T::InherentDataProviders,
+				>,
+			>,
+		),
+		sc_service::Error,
+	>,
+{
+	let heap_pages = config
+		.default_heap_pages
+		.map(|pages| HeapAllocStrategy::Static { extra_pages: pages as _ })
+		.unwrap_or(DEFAULT_HEAP_ALLOC_STRATEGY);
+	// Signed extrinsics submitted through simnode carry no real signature (only the sender's
+	// account is given); `SignatureVerificationOverride` replaces the signature-verification host
+	// functions so they accept them. Without it those extrinsics fail verification.
+	let executor = WasmExecutor::<(SubstrateHostFunctions, SignatureVerificationOverride)>::builder()
+		.with_execution_method(config.wasm_method)
+		.with_max_runtime_instances(config.max_runtime_instances)
+		.with_onchain_heap_alloc_strategy(heap_pages)
+		.with_offchain_heap_alloc_strategy(heap_pages)
+		.build();
+
+	let (node, _network, _keystore, ()) = build_node_subsystems_with_executor::<T, I, _, _>(
+		config,
+		is_parachain,
+		block_import_provider,
+		executor,
+		|_client| (Vec::new(), ()),
+	)?;
+	Ok(node)
+}
+
+/// Shared implementation backing [`build_node_subsystems`] and [`build_node_subsystems_wasm`],
+/// parameterised over the executor so the native/WASM split lives only in how `executor` is built.
+///
+/// `configure_extra_network_protocols` runs once `client` exists but before the network is built,
+/// returning any extra [`sc_network::config::NonDefaultSetConfig`]s to register (e.g. a gossip
+/// protocol keyed off the client's genesis hash) alongside arbitrary state `R` a caller needs after
+/// the real `network`/`client` handles below are available — see
+/// [`crate::statement_store::build_node_subsystems_with_statement_store`] for why this is a closure
+/// and not just a `Vec`.
+pub(crate) fn build_node_subsystems_with_executor<T, I, E, R>(
+	config: Configuration,
+	is_parachain: bool,
+	block_import_provider: I,
+	executor: E,
+	configure_extra_network_protocols: impl FnOnce(
+		&Arc<FullClientFor<T>>,
+	) -> (Vec<sc_network::config::NonDefaultSetConfig>, R),
+) -> Result<
+	(Node<T>, Arc<sc_network::NetworkService<T::Block, <T::Block as BlockT>::Hash>>, sp_keystore::SyncCryptoStorePtr, R),
+	sc_service::Error,
+>
+where
+	T: ChainInfo + 'static,
+	E: CodeExecutor + RuntimeVersionOf + Clone + Send + Sync + 'static,
+	<T::RuntimeApi as ConstructRuntimeApi<T::Block, FullClientFor<T>>>::RuntimeApi:
+		Core<T::Block>
+			+ Metadata<T::Block>
+			+ OffchainWorkerApi<T::Block>
+			+ SessionKeys<T::Block>
+			+ TaggedTransactionQueue<T::Block>
+			+ BlockBuilder<T::Block>
+			+ ApiExt<T::Block, StateBackend = <TFullBackend<T::Block> as Backend<T::Block>>::State>,
+	<T::Runtime as frame_system::Config>::Call: From<frame_system::Call<T::Runtime>>,
+	<<T as ChainInfo>::Block as BlockT>::Hash: FromStr + Unpin,
+	<<T as ChainInfo>::Block as BlockT>::Header: Unpin,
+	<<<T as ChainInfo>::Block as BlockT>::Header as Header>::Number:
+		num_traits::cast::AsPrimitive<usize> + num_traits::cast::AsPrimitive<u32>,
+	I: Fn(
+		Arc<FullClientFor<T>>,
+		sc_consensus::LongestChain<TFullBackend<T::Block>, T::Block>,
+		&KeystoreContainer,
+		Option<SharedParachainInherentProvider<T>>,
+	) -> Result<
+		(
+			T::BlockImport,
+			Option<
+				Box<
+					dyn ConsensusDataProvider<
+						T::Block,
+						Transaction = TransactionFor<FullClientFor<T>, T::Block>,
+					>,
+				>,
+			>,
+			Box<
+				dyn CreateInherentDataProviders<
+					T::Block,
+					(),
+					InherentDataProviders = T::InherentDataProviders,
+				>,
+			>,
+		),
+		sc_service::Error,
+	>,
+{
+	let mut config = config;
 	let (client, backend, keystore, mut task_manager) =
 		new_full_parts::<T::Block, T::RuntimeApi, _>(&config, None, executor)?;
 	let client = Arc::new(client);
 
+	let (extra_network_protocols, extra_state) = configure_extra_network_protocols(&client);
+	config.network.extra_sets.extend(extra_network_protocols);
+
 	let select_chain = sc_consensus::LongestChain::new(backend.clone());
 
 	let parachain_inherent_provider = if is_parachain {
@@ -140,6 +303,8 @@ where
 		};
 		build_network(params)?
 	};
+	let network_handle = network.clone();
+	let keystore_ptr = keystore.sync_keystore();
 
 	// offchain workers
 	sc_service::build_offchain_workers(
@@ -214,5 +379,43 @@ where
 		parachain_inherent_provider,
 	};
 
-	Ok(node)
+	Ok((node, network_handle, keystore_ptr, extra_state))
+}
+
+/// Builds the [`ConsensusDataProvider`] and [`CreateInherentDataProviders`] needed to drive an
+/// Aura (+ GRANDPA) chain through manual seal.
+///
+/// This mirrors the `BabeConsensusDataProvider` / `SlotTimestampProvider::babe` wiring a
+/// `block_import_provider` has to hand-roll for BABE chains, so `ChainInfo` implementations for
+/// Aura-based runtimes (node-template and its derivatives) get `seal_blocks` working without
+/// reimplementing this plumbing themselves.
+pub fn aura_consensus_data_provider<T>(
+	client: Arc<FullClientFor<T>>,
+) -> (
+	Box<dyn ConsensusDataProvider<T::Block, Transaction = TransactionFor<FullClientFor<T>, T::Block>>>,
+	Box<
+		dyn CreateInherentDataProviders<
+			T::Block,
+			(),
+			InherentDataProviders = (SlotTimestampProvider, AuraInherentDataProvider),
+		>,
+	>,
+)
+where
+	T: ChainInfo,
+	<T::RuntimeApi as ConstructRuntimeApi<T::Block, FullClientFor<T>>>::RuntimeApi: AuraApi<T::Block, AuraId>,
+{
+	let consensus_data_provider = AuraConsensusDataProvider::new(client.clone());
+
+	let create_inherent_data_providers = Box::new(move |_, _| {
+		let client = client.clone();
+		async move {
+			let timestamp =
+				SlotTimestampProvider::aura(client.clone()).map_err(|err| format!("{:?}", err))?;
+			let aura = AuraInherentDataProvider::new(timestamp.slot().into());
+			Ok((timestamp, aura))
+		}
+	});
+
+	(Box::new(consensus_data_provider), create_inherent_data_providers)
 }
@@ -0,0 +1,74 @@
+// Copyright (C) 2021 Polytope Capital (Caymans) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Explicit block-finalization control for [`Node`], on top of the authorship control
+//! [`Node::seal_blocks`](crate::node::Node::seal_blocks) already provides.
+
+use crate::{ChainInfo, Node};
+use manual_seal::EngineCommand;
+use sp_api::BlockId;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header};
+
+impl<T> Node<T>
+where
+	T: ChainInfo,
+	<<T as ChainInfo>::Block as BlockT>::Header: Header,
+	<<<T as ChainInfo>::Block as BlockT>::Header as Header>::Number: num_traits::cast::AsPrimitive<usize>,
+{
+	/// Finalizes the block at `hash`, via the same `EngineCommand::FinalizeBlock` manual seal
+	/// already uses to service the `engine_finalizeBlock` RPC.
+	///
+	/// Finality is cumulative, so finalizing `hash` also finalizes every one of its ancestors.
+	pub async fn finalize_block(&self, hash: <T::Block as BlockT>::Hash) {
+		let (sender, receiver) = futures::channel::oneshot::channel();
+		self.manual_seal_command_sink
+			.clone()
+			.try_send(EngineCommand::FinalizeBlock { hash, sender: Some(sender), justification: None })
+			.expect("manual seal authorship task outlives `Node`");
+		receiver
+			.await
+			.expect("finalization request was dropped before a reply was sent")
+			.expect("failed to finalize block");
+	}
+
+	/// Finalizes the block `count` blocks ahead of the currently finalized tip, along the best
+	/// chain, finalizing every block in between as a side effect. If the best chain is shorter
+	/// than that, the best block is finalized instead.
+	pub async fn finalize_blocks(&self, count: usize) {
+		let info = self.client.info();
+		let target_number = info.finalized_number.as_() + count;
+
+		let mut target = info.best_hash;
+		while let Ok(Some(header)) = self.client.header(BlockId::Hash(target)) {
+			if header.number().as_() <= target_number {
+				break
+			}
+			target = *header.parent_hash();
+		}
+
+		self.finalize_block(target).await;
+	}
+
+	/// Seals `count` new blocks, finalizing each one as soon as it's authored.
+	pub async fn seal_blocks_and_finalize(&self, count: usize) {
+		for _ in 0..count {
+			self.seal_blocks(1).await;
+			let best_hash = self.client.info().best_hash;
+			self.finalize_block(best_hash).await;
+		}
+	}
+}
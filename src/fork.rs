@@ -0,0 +1,62 @@
+// Copyright (C) 2021 Polytope Capital (Caymans) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fork/reorg simulation: sealing on an explicit parent hash instead of always extending the
+//! current best chain, so a test can grow a shorter side-chain and then extend it past the
+//! canonical one to trigger a reorg.
+
+use crate::{ChainInfo, Node};
+use manual_seal::{rpc::CreatedBlock, EngineCommand};
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::BlockId;
+use sp_runtime::traits::Block as BlockT;
+
+impl<T: ChainInfo> Node<T> {
+	/// Seals a new block on top of `parent_hash` instead of the current best block, allowing a
+	/// competing branch to be grown alongside the canonical chain.
+	pub async fn seal_block_on(
+		&self,
+		parent_hash: <T::Block as BlockT>::Hash,
+		create_empty: bool,
+		finalize: bool,
+	) -> CreatedBlock<<T::Block as BlockT>::Hash> {
+		let (sender, receiver) = futures::channel::oneshot::channel();
+		self.manual_seal_command_sink
+			.clone()
+			.try_send(EngineCommand::SealNewBlock {
+				create_empty,
+				finalize,
+				parent_hash: Some(parent_hash),
+				sender: Some(sender),
+			})
+			.expect("manual seal authorship task outlives `Node`");
+		receiver
+			.await
+			.expect("seal request was dropped before a reply was sent")
+			.expect("failed to seal block on parent_hash")
+	}
+
+	/// Submits an already-built extrinsic into the pool against the state at `parent_hash`,
+	/// rather than the current best block, so it is picked up by [`Node::seal_block_on`] when
+	/// extending that specific branch.
+	pub async fn submit_extrinsic_on(
+		&self,
+		parent_hash: <T::Block as BlockT>::Hash,
+		extrinsic: <T::Block as BlockT>::Extrinsic,
+	) -> Result<<T::Block as BlockT>::Hash, sc_transaction_pool_api::error::Error> {
+		self.pool.submit_one(&BlockId::Hash(parent_hash), TransactionSource::External, extrinsic).await
+	}
+}
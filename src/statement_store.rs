@@ -0,0 +1,195 @@
+// Copyright (C) 2021 Polytope Capital (Caymans) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Optional `sp-statement-store` wiring, for testing pallets that read/write gossiped statements
+//! through `sc-offchain` host functions without a real network.
+//!
+//! Unlike the other node-building helpers in this crate, this one can't just take an already-built
+//! [`Node`]: `sc-network-statement`'s gossip protocol has to be registered with the network
+//! *before* it's built, and the store itself needs the real `network`/`client` handles that only
+//! exist once [`crate::client::build_node_subsystems_with_executor`] has run. So
+//! [`build_node_subsystems_with_statement_store`] drives that shared builder itself, threading a
+//! [`sc_network_statement::StatementHandlerPrototype`] through as the builder's extra state.
+
+use crate::{
+	client::build_node_subsystems_with_executor, ChainInfo, FullClientFor,
+	SharedParachainInherentProvider, Node,
+};
+use manual_seal::ConsensusDataProvider;
+use sc_client_api::{backend::Backend, execution_extensions::ExtensionsFactory};
+use sc_executor::NativeElseWasmExecutor;
+use sc_service::{Configuration, KeystoreContainer, TFullBackend};
+use sp_api::{ApiExt, ConstructRuntimeApi, Core, Metadata, TransactionFor};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_externalities::Extensions;
+use sp_inherents::CreateInherentDataProviders;
+use sp_offchain::OffchainWorkerApi;
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
+use sp_session::SessionKeys;
+use sp_statement_store::{Hash, Statement, SubmitResult};
+use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
+use std::{str::FromStr, sync::Arc};
+
+/// A disk-backed [`sc_statement_store::Store`] attached to a [`Node`] in a fresh temporary
+/// directory, registered as the extension pallets' `sp_statement_store` host function calls
+/// reach, with test-side helpers to inject and inspect statements directly.
+///
+/// Dropping this handle removes its temporary directory.
+pub struct StatementStoreHandle {
+	store: Arc<sc_statement_store::Store>,
+	path: std::path::PathBuf,
+}
+
+impl Drop for StatementStoreHandle {
+	fn drop(&mut self) {
+		let _ = std::fs::remove_dir_all(&self.path);
+	}
+}
+
+/// Hands `store` to the runtime's statement-store host functions on every block, the same way
+/// [`sc_offchain`]'s own extensions are installed — a one-shot `register_extension` call on
+/// `client` wouldn't reach the per-invocation `Extensions` the host functions actually read.
+struct StatementStoreExtensionsFactory(Arc<sc_statement_store::Store>);
+
+impl<Block: BlockT> ExtensionsFactory<Block> for StatementStoreExtensionsFactory {
+	fn extensions_for(
+		&self,
+		_block_hash: Block::Hash,
+		_block_number: NumberFor<Block>,
+	) -> Extensions {
+		let mut extensions = Extensions::new();
+		extensions.register(sp_statement_store::runtime_api::StatementStoreExt(self.0.clone()));
+		extensions
+	}
+}
+
+impl StatementStoreHandle {
+	/// Submits `statement` to the store, as if it had just been gossiped in from the network.
+	pub fn submit_statement(&self, statement: Statement) -> SubmitResult {
+		self.store.submit(statement, sp_statement_store::StatementSource::Local)
+	}
+
+	/// All statements currently held by the store.
+	pub fn statements(&self) -> Vec<(Hash, Statement)> {
+		self.store.statements().unwrap_or_default()
+	}
+}
+
+/// Like [`crate::client::build_node_subsystems`], but also wires up an
+/// [`sc_statement_store::Store`]: the store's extension is installed so the runtime's
+/// `sp_statement_store` host functions read from it, and `sc-network-statement`'s gossip protocol
+/// is registered with the network and spawned, so statements submitted locally via
+/// [`StatementStoreHandle::submit_statement`] actually propagate.
+pub fn build_node_subsystems_with_statement_store<T, I>(
+	config: Configuration,
+	is_parachain: bool,
+	block_import_provider: I,
+) -> Result<(Node<T>, StatementStoreHandle), sc_service::Error>
+where
+	T: ChainInfo + 'static,
+	<T::RuntimeApi as ConstructRuntimeApi<T::Block, FullClientFor<T>>>::RuntimeApi:
+		Core<T::Block>
+			+ Metadata<T::Block>
+			+ OffchainWorkerApi<T::Block>
+			+ SessionKeys<T::Block>
+			+ TaggedTransactionQueue<T::Block>
+			+ BlockBuilder<T::Block>
+			+ ApiExt<T::Block, StateBackend = <TFullBackend<T::Block> as Backend<T::Block>>::State>,
+	<T::Runtime as frame_system::Config>::Call: From<frame_system::Call<T::Runtime>>,
+	<<T as ChainInfo>::Block as BlockT>::Hash: FromStr + Unpin,
+	<<T as ChainInfo>::Block as BlockT>::Header: Unpin,
+	<<<T as ChainInfo>::Block as BlockT>::Header as Header>::Number:
+		num_traits::cast::AsPrimitive<usize> + num_traits::cast::AsPrimitive<u32>,
+	I: Fn(
+		Arc<FullClientFor<T>>,
+		sc_consensus::LongestChain<TFullBackend<T::Block>, T::Block>,
+		&KeystoreContainer,
+		Option<SharedParachainInherentProvider<T>>,
+	) -> Result<
+		(
+			T::BlockImport,
+			Option<
+				Box<
+					dyn ConsensusDataProvider<
+						T::Block,
+						Transaction = TransactionFor<FullClientFor<T>, T::Block>,
+					>,
+				>,
+			>,
+			Box<
+				dyn CreateInherentDataProviders<
+					T::Block,
+					(),
+					InherentDataProviders = T::InherentDataProviders,
+				>,
+			>,
+		),
+		sc_service::Error,
+	>,
+{
+	let executor = NativeElseWasmExecutor::<T::ExecutorDispatch>::new(
+		config.wasm_method,
+		config.default_heap_pages,
+		config.max_runtime_instances,
+	);
+
+	// Registering the gossip protocol has to happen before `build_network` runs, but the
+	// `StatementHandlerPrototype` it returns can only be driven (`.build`) once the real network
+	// handle exists afterwards — so it rides through as the shared builder's extra state.
+	let (node, network, keystore, prototype) = build_node_subsystems_with_executor::<T, I, _, _>(
+		config,
+		is_parachain,
+		block_import_provider,
+		executor,
+		|client| {
+			let (prototype, protocol_config) =
+				sc_network_statement::StatementHandlerPrototype::new(client.info().genesis_hash, None);
+			(vec![protocol_config], prototype)
+		},
+	)?;
+
+	static NEXT_STORE_ID: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+	let path = std::env::temp_dir().join(format!(
+		"simnode-statement-store-{}-{}",
+		std::process::id(),
+		NEXT_STORE_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+	));
+	let store = Arc::new(
+		sc_statement_store::Store::new_shared(
+			&path,
+			Default::default(),
+			node.client(),
+			keystore,
+			None,
+			node.task_manager().spawn_handle(),
+		)
+		.map_err(|err| sc_service::Error::Application(Box::new(err)))?,
+	);
+
+	node.client()
+		.execution_extensions()
+		.set_extensions_factory(StatementStoreExtensionsFactory(store.clone()));
+
+	let handler = prototype
+		.build(network.clone(), network, store.clone(), None)
+		.map_err(|err| sc_service::Error::Application(Box::new(err)))?;
+	node.task_manager()
+		.spawn_handle()
+		.spawn("statement-store-gossip", None, handler.run());
+
+	Ok((node, StatementStoreHandle { store, path }))
+}
@@ -0,0 +1,209 @@
+// Copyright (C) 2021 Polytope Capital (Caymans) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fast-forwarding BABE slots/epochs without sealing every intervening block.
+//!
+//! Sealing thousands of blocks one at a time to test session rotation, era changes, or far-future
+//! `pallet_scheduler` tasks is prohibitively slow. [`WarpableNode`] instead carries a mutable slot
+//! offset that the `create_inherent_data_providers` closure reads on every call (see
+//! [`SlotOffset::apply`]), and only authors the minimal number of boundary blocks BABE's
+//! epoch-change logic actually needs (one per epoch transition crossed), plus the block that
+//! lands on the target slot, to get there —
+//! `epoch_changes` must never skip an epoch descriptor, or import fails with `FetchEpoch`.
+//!
+//! This only fast-forwards slot-derived state (sessions, eras, BABE/GRANDPA epochs). The
+//! `pallet_timestamp` moment a block carries is produced by a *separate* inherent
+//! (`SlotTimestampProvider` itself, not the [`SlotOffset`] layered on top of the slot it reports),
+//! so the moment a block carries is derived from its parent's slot without the pending delta:
+//! the block a warp lands on carries an unwarped `pallet_timestamp` moment, and `Moment`-based
+//! logic (e.g. linear vesting schedules) only sees the jump from the block after it, whose parent
+//! slot already carries the warp.
+//!
+//! Because `create_inherent_data_providers` is built once, inside `build_node_subsystems`, well
+//! before a [`WarpableNode`] exists, the [`SlotOffset`] has to be created *first* and shared with
+//! both sides: clone it into that closure (which must call [`SlotOffset::apply`] on every slot it
+//! derives from the parent block) and hand the same value to [`WarpableNode::new`].
+
+use crate::{ChainInfo, Node};
+use sc_consensus_babe::{descendent_query, Config as BabeConfig, Epoch, SharedEpochChanges};
+use sp_consensus_slots::Slot;
+use std::{
+	ops::Deref,
+	sync::{
+		atomic::{AtomicI64, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+
+/// A shared cell threading target-slot warps into the BABE `create_inherent_data_providers`
+/// closure.
+///
+/// `SlotTimestampProvider::babe` derives each block's raw slot from its parent block's pre-digest
+/// slot (already one ahead, and already carrying any previous warp), not the wall clock. So
+/// instead of an offset added to every call forever (which would double-count: the parent slot
+/// already reflects it), [`apply`](SlotOffset::apply) tracks the running chain-tip slot itself and
+/// [`set_target`](SlotOffset::set_target) stores a *one-shot* delta that `apply` consumes and
+/// clears on its very next call.
+///
+/// Construct this *before* the node (with [`SlotOffset::new`]), move a clone into the BABE
+/// `create_inherent_data_providers` closure, and pass the same value to [`WarpableNode::new`] —
+/// the two sides must share the same underlying cell or warping is a no-op.
+#[derive(Clone)]
+pub struct SlotOffset(Arc<SlotOffsetInner>);
+
+struct SlotOffsetInner {
+	/// A one-shot delta added to the next slot `apply` sees, then reset to zero.
+	pending_delta: AtomicI64,
+	/// The most recent slot `apply` returned, i.e. the chain tip's current slot.
+	last_applied: AtomicI64,
+}
+
+impl SlotOffset {
+	/// Creates a fresh offset, initially a no-op.
+	pub fn new() -> Self {
+		Self(Arc::new(SlotOffsetInner {
+			pending_delta: AtomicI64::new(0),
+			last_applied: AtomicI64::new(0),
+		}))
+	}
+
+	/// Adds any pending one-shot delta to `slot` and clears it, as a
+	/// `create_inherent_data_providers` closure should do to every slot it derives from the parent
+	/// block before handing it to BABE.
+	pub fn apply(&self, slot: Slot) -> Slot {
+		let delta = self.0.pending_delta.swap(0, Ordering::SeqCst);
+		let applied = Slot::from((u64::from(slot) as i64 + delta).max(0) as u64);
+		self.0.last_applied.store(u64::from(applied) as i64, Ordering::SeqCst);
+		applied
+	}
+
+	/// The slot the next `apply` call would return, absent an intervening [`set_target`](Self::set_target).
+	fn next_slot(&self) -> Slot {
+		Slot::from(self.0.last_applied.load(Ordering::SeqCst) as u64 + 1)
+	}
+
+	/// Arranges for the *next* [`apply`](Self::apply) call to return `target_slot`, computing the
+	/// one-shot delta relative to [`next_slot`](Self::next_slot) rather than the wall clock.
+	fn set_target(&self, target_slot: Slot) {
+		let delta = u64::from(target_slot) as i64 - u64::from(self.next_slot()) as i64;
+		self.0.pending_delta.store(delta, Ordering::SeqCst);
+	}
+}
+
+impl Default for SlotOffset {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// A [`Node`] paired with the [`SlotOffset`] and BABE epoch bookkeeping needed to fast-forward
+/// slots/epochs via [`WarpableNode::warp_to_slot`] / [`WarpableNode::warp_by`].
+///
+/// Derefs to [`Node`], so all the usual `seal_blocks`/`submit_extrinsic`/`with_state` helpers
+/// remain available.
+pub struct WarpableNode<T: ChainInfo> {
+	node: Node<T>,
+	offset: SlotOffset,
+	epoch_changes: SharedEpochChanges<T::Block, Epoch>,
+	babe_config: BabeConfig,
+}
+
+impl<T: ChainInfo> Deref for WarpableNode<T> {
+	type Target = Node<T>;
+
+	fn deref(&self) -> &Node<T> {
+		&self.node
+	}
+}
+
+impl<T: ChainInfo> WarpableNode<T> {
+	/// Wraps `node`, sharing `offset` with the BABE `create_inherent_data_providers` closure used
+	/// when the node was built (see the module docs) — `offset` and `epoch_changes` should be the
+	/// same values the closure and `BabeConsensusDataProvider` were constructed with, and
+	/// `babe_config` the `sc_consensus_babe::Config` used to build that provider.
+	pub fn new(
+		node: Node<T>,
+		offset: SlotOffset,
+		epoch_changes: SharedEpochChanges<T::Block, Epoch>,
+		babe_config: BabeConfig,
+	) -> Self {
+		Self { node, offset, epoch_changes, babe_config }
+	}
+
+	/// The slot the next authored block would currently carry, absent a new warp target.
+	pub fn current_slot(&self) -> Slot {
+		self.offset.next_slot()
+	}
+
+	/// Fast-forwards until an authored block lands on `target_slot`, authoring only the boundary
+	/// blocks BABE's epoch-change logic requires along the way plus that final landing block.
+	/// Returns the number of blocks actually authored.
+	pub async fn warp_to_slot(&self, target_slot: Slot) -> usize {
+		let mut authored = 0;
+
+		loop {
+			let current = self.current_slot();
+			if current >= target_slot {
+				break
+			}
+
+			// Never skip an epoch descriptor: if another epoch boundary lies strictly between
+			// `current` and `target_slot`, land exactly on it and author one block there first.
+			let next_stop = self
+				.next_epoch_boundary_after(current)
+				.filter(|boundary| *boundary < target_slot)
+				.unwrap_or(target_slot);
+
+			self.offset.set_target(next_stop);
+			self.node.seal_blocks(1).await;
+			authored += 1;
+		}
+
+		authored
+	}
+
+	/// Fast-forwards the clock by `duration`, in slot-duration increments.
+	pub async fn warp_by(&self, duration: Duration) -> usize {
+		let slots = duration.as_millis() as u64 / self.babe_config.slot_duration().as_millis() as u64;
+		self.warp_to_slot(Slot::from(u64::from(self.current_slot()) + slots)).await
+	}
+
+	/// The slot at which the epoch containing `slot` ends, i.e. the next slot BABE's
+	/// epoch-change logic needs a block authored on in order to insert a `NextEpochDescriptor`.
+	///
+	/// Falls back to deriving a fresh genesis epoch from the real `babe_config` (not a default
+	/// one, whose zero `epoch_duration` would make every slot look like a boundary and spin
+	/// `warp_to_slot` forever).
+	fn next_epoch_boundary_after(&self, slot: Slot) -> Option<Slot> {
+		let best_hash = self.node.client().info().best_hash;
+		let babe_config = &self.babe_config;
+		let epoch = self
+			.epoch_changes
+			.shared_data()
+			.epoch_data_for_child_of(
+				descendent_query(&*self.node.client()),
+				&best_hash,
+				self.node.client().info().best_number.into(),
+				slot,
+				|slot| Epoch::genesis(babe_config, slot),
+			)
+			.ok()
+			.flatten()?;
+
+		Some(epoch.start_slot() + epoch.duration())
+	}
+}